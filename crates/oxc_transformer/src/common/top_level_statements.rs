@@ -9,10 +9,21 @@
 //! ```rs
 //! self.ctx.top_level_statements.insert_statement(stmt);
 //! ```
+//!
+//! To make a binding from a module available without producing redundant `import` declarations,
+//! use `TopLevelStatementsStore::insert_import` instead. Requests for the same module source are
+//! merged into a single `import` and duplicate specifiers are collapsed:
+//!
+//! ```rs
+//! self.ctx.top_level_statements.insert_import(local, imported, source);
+//! ```
 
 use std::cell::RefCell;
 
+use rustc_hash::{FxHashMap, FxHashSet};
+
 use oxc_ast::ast::*;
+use oxc_span::{Atom, SPAN};
 use oxc_traverse::{Traverse, TraverseCtx};
 
 use crate::TransformCtx;
@@ -20,51 +31,452 @@ use crate::TransformCtx;
 /// Transform that inserts any statements which have been requested insertion via `TopLevelStatementsStore`
 /// to top of the program.
 ///
-/// Insertions are made after any existing `import` statements.
+/// Insertions are made after any existing `import` statements, unless another [`Position`] was
+/// chosen with `insert_statement_at`. Pending imports registered with
+/// `insert_import` are merged into existing `import` declarations where possible.
 ///
 /// Must run after all other transforms.
 pub struct TopLevelStatements<'a, 'ctx> {
     ctx: &'ctx TransformCtx<'a>,
+    /// When enabled, `import` declarations scattered throughout the program are hoisted back into
+    /// the leading import block before insertions are made.
+    hoist_imports: bool,
 }
 
 impl<'a, 'ctx> TopLevelStatements<'a, 'ctx> {
     pub fn new(ctx: &'ctx TransformCtx<'a>) -> Self {
-        Self { ctx }
+        Self { ctx, hoist_imports: false }
+    }
+
+    /// Enable hoisting of scattered `import` declarations to the top of the program.
+    #[must_use]
+    pub fn with_hoisted_imports(mut self) -> Self {
+        self.hoist_imports = true;
+        self
     }
 }
 
 impl<'a, 'ctx> Traverse<'a> for TopLevelStatements<'a, 'ctx> {
-    fn exit_program(&mut self, program: &mut Program<'a>, _ctx: &mut TraverseCtx<'a>) {
-        let mut stmts = self.ctx.top_level_statements.stmts.borrow_mut();
-        if stmts.is_empty() {
+    fn exit_program(&mut self, program: &mut Program<'a>, ctx: &mut TraverseCtx<'a>) {
+        if self.hoist_imports {
+            hoist_scattered_imports(&mut program.body);
+        }
+        self.insert_imports(program, ctx);
+        self.insert_statements(program);
+    }
+}
+
+impl<'a, 'ctx> TopLevelStatements<'a, 'ctx> {
+    /// Merge pending imports into the program, reusing an existing `import` of the same source
+    /// where one exists and synthesizing a fresh declaration otherwise.
+    fn insert_imports(&self, program: &mut Program<'a>, ctx: &mut TraverseCtx<'a>) {
+        let mut imports = self.ctx.top_level_statements.imports.borrow_mut();
+        if imports.is_empty() {
             return;
         }
 
-        // Insert statements after any existing `import` statements
-        let index = program
-            .body
-            .iter()
-            .rposition(|stmt| matches!(stmt, Statement::ImportDeclaration(_)))
-            .map_or(0, |i| i + 1);
+        for (source, pending) in imports.drain() {
+            // Append to an existing named `import` of the same source if there is one. Imports with
+            // a default or namespace specifier can't take extra named specifiers, so they're left
+            // alone and a fresh declaration is synthesized instead.
+            if let Some(import) = program.body.iter_mut().find_map(|stmt| match stmt {
+                Statement::ImportDeclaration(import)
+                    if import.source.value == source && is_mergeable_import(import) =>
+                {
+                    Some(import)
+                }
+                _ => None,
+            }) {
+                let specifiers = import.specifiers.get_or_insert_with(|| ctx.ast.vec());
+                for (imported, local) in pending.specifiers {
+                    // Skip specifiers the declaration already carries, so a request for a binding
+                    // that's already imported doesn't produce a duplicate.
+                    if !named_specifier_exists(specifiers, imported, local) {
+                        specifiers.push(Self::import_specifier(ctx, imported, local));
+                    }
+                }
+                continue;
+            }
 
-        program.body.splice(index..index, stmts.drain(..));
+            // Otherwise synthesize a fresh `import` statement after the last existing import.
+            let specifiers = ctx.ast.vec_from_iter(
+                pending
+                    .specifiers
+                    .into_iter()
+                    .map(|(imported, local)| Self::import_specifier(ctx, imported, local)),
+            );
+            let import = ctx.ast.alloc_import_declaration(
+                SPAN,
+                Some(specifiers),
+                ctx.ast.string_literal(SPAN, source, None),
+                None,
+                ImportOrExportKind::Value,
+            );
+            let (_, index) = import_anchors(&program.body);
+            program.body.insert(index, Statement::ImportDeclaration(import));
+        }
+    }
+
+    /// Splice each bucket of queued statements at its anchor.
+    ///
+    /// The first and last import indices are computed in a single pass over `program.body`. The
+    /// directive prologue lives in `program.directives`, not in `body`, so the `AfterDirectives`
+    /// anchor is simply the start of the body. Buckets are spliced from the bottom up so that the
+    /// anchors computed for higher buckets remain valid while lower ones are inserted.
+    fn insert_statements(&self, program: &mut Program<'a>) {
+        let store = &self.ctx.top_level_statements;
+        let mut after_imports = store.stmts.borrow_mut();
+        let mut before_imports = store.before_imports.borrow_mut();
+        let mut after_directives = store.after_directives.borrow_mut();
+        if after_imports.is_empty() && before_imports.is_empty() && after_directives.is_empty() {
+            return;
+        }
+
+        let (before_imports_index, after_imports_index) = import_anchors(&program.body);
+
+        program.body.splice(after_imports_index..after_imports_index, after_imports.drain(..));
+        program.body.splice(before_imports_index..before_imports_index, before_imports.drain(..));
+        program.body.splice(0..0, after_directives.drain(..));
+    }
+
+    /// Build a named `import` specifier for `local` / `imported`.
+    fn import_specifier(
+        ctx: &TraverseCtx<'a>,
+        imported: Atom<'a>,
+        local: Atom<'a>,
+    ) -> ImportDeclarationSpecifier<'a> {
+        let imported = ctx.ast.module_export_name_identifier_name(SPAN, imported);
+        let local = ctx.ast.binding_identifier(SPAN, local);
+        ImportDeclarationSpecifier::ImportSpecifier(ctx.ast.alloc_import_specifier(
+            SPAN,
+            imported,
+            local,
+            ImportOrExportKind::Value,
+        ))
     }
 }
 
+/// Move every `import` declaration that appears after a non-import statement up into the leading
+/// import block, preserving each import's relative order.
+///
+/// Leading comments travel with each statement, as they are keyed to the import's span rather than
+/// to its position in `body`.
+fn hoist_scattered_imports<'a>(body: &mut oxc_allocator::Vec<'a, Statement<'a>>) {
+    // End of the contiguous leading block of imports.
+    let lead = body
+        .iter()
+        .position(|stmt| !matches!(stmt, Statement::ImportDeclaration(_)))
+        .unwrap_or(body.len());
+
+    // Collect the scattered imports in order, removing them from their original positions.
+    let mut hoisted: Vec<Statement<'a>> = vec![];
+    let mut i = lead;
+    while i < body.len() {
+        if matches!(body[i], Statement::ImportDeclaration(_)) {
+            hoisted.push(body.remove(i));
+        } else {
+            i += 1;
+        }
+    }
+
+    // Re-insert them at the end of the leading import block.
+    body.splice(lead..lead, hoisted);
+}
+
+/// Indices of the first `import` and of the position just after the last `import` in `body`.
+///
+/// Both default to `0` when there are no imports.
+fn import_anchors<'a>(body: &[Statement<'a>]) -> (usize, usize) {
+    let mut first_import = None;
+    let mut last_import = None;
+    for (i, stmt) in body.iter().enumerate() {
+        if matches!(stmt, Statement::ImportDeclaration(_)) {
+            first_import.get_or_insert(i);
+            last_import = Some(i);
+        }
+    }
+    (first_import.unwrap_or(0), last_import.map_or(0, |i| i + 1))
+}
+
+/// Whether new named specifiers may be merged into `import`.
+///
+/// Only imports with no specifiers or exclusively named specifiers can take extra named
+/// specifiers; appending to a default or namespace import would produce invalid syntax.
+fn is_mergeable_import<'a>(import: &ImportDeclaration<'a>) -> bool {
+    import.specifiers.as_ref().is_none_or(|specifiers| {
+        specifiers.iter().all(|s| matches!(s, ImportDeclarationSpecifier::ImportSpecifier(_)))
+    })
+}
+
+/// Whether `specifiers` already contains a named specifier for the `(imported, local)` pair.
+fn named_specifier_exists<'a>(
+    specifiers: &[ImportDeclarationSpecifier<'a>],
+    imported: Atom<'a>,
+    local: Atom<'a>,
+) -> bool {
+    specifiers.iter().any(|specifier| match specifier {
+        ImportDeclarationSpecifier::ImportSpecifier(s) => {
+            s.local.name == local && module_export_name_atom(&s.imported) == imported
+        }
+        _ => false,
+    })
+}
+
+/// Name of a `ModuleExportName`, regardless of which form it takes.
+fn module_export_name_atom<'a>(name: &ModuleExportName<'a>) -> Atom<'a> {
+    match name {
+        ModuleExportName::IdentifierName(id) => id.name,
+        ModuleExportName::IdentifierReference(id) => id.name,
+        ModuleExportName::StringLiteral(lit) => lit.value,
+    }
+}
+
+/// A set of import specifiers pending insertion for a single module source.
+///
+/// Specifiers are de-duplicated on their `(imported, local)` pair so that repeated requests for
+/// the same binding collapse to a single specifier.
+struct PendingImport<'a> {
+    /// `(imported, local)` specifiers to add, in request order.
+    specifiers: Vec<(Atom<'a>, Atom<'a>)>,
+    /// `(imported, local)` pairs already requested.
+    seen: FxHashSet<(Atom<'a>, Atom<'a>)>,
+}
+
+impl<'a> PendingImport<'a> {
+    fn new() -> Self {
+        Self { specifiers: vec![], seen: FxHashSet::default() }
+    }
+
+    /// Record a specifier, ignoring exact duplicates.
+    fn push(&mut self, imported: Atom<'a>, local: Atom<'a>) {
+        if self.seen.insert((imported, local)) {
+            self.specifiers.push((imported, local));
+        }
+    }
+}
+
+/// Anchor controlling where a statement inserted via [`TopLevelStatementsStore::insert_statement_at`]
+/// is spliced into the program.
+#[derive(Clone, Copy)]
+pub enum Position {
+    /// Before all `import` statements, but after the directive prologue. Use for side-effect
+    /// imports or polyfills that must run before other modules are loaded.
+    BeforeImports,
+    /// Immediately after the directive prologue (`"use strict"` and friends), before any `import`.
+    AfterDirectives,
+    /// After all `import` statements. This is where [`TopLevelStatementsStore::insert_statement`]
+    /// inserts.
+    AfterImports,
+}
+
 /// Store for statements to be added at top of program
 pub struct TopLevelStatementsStore<'a> {
+    /// Statements anchored after all imports.
     stmts: RefCell<Vec<Statement<'a>>>,
+    /// Statements anchored before all imports.
+    before_imports: RefCell<Vec<Statement<'a>>>,
+    /// Statements anchored after the directive prologue.
+    after_directives: RefCell<Vec<Statement<'a>>>,
+    /// Keys of statements already queued via `insert_statement_once`.
+    stmt_keys: RefCell<FxHashSet<Atom<'a>>>,
+    imports: RefCell<FxHashMap<Atom<'a>, PendingImport<'a>>>,
 }
 
 impl<'a> TopLevelStatementsStore<'a> {
     pub fn new() -> Self {
-        Self { stmts: RefCell::new(vec![]) }
+        Self {
+            stmts: RefCell::new(vec![]),
+            before_imports: RefCell::new(vec![]),
+            after_directives: RefCell::new(vec![]),
+            stmt_keys: RefCell::new(FxHashSet::default()),
+            imports: RefCell::new(FxHashMap::default()),
+        }
     }
 }
 
 impl<'a> TopLevelStatementsStore<'a> {
-    /// Add a statement to be inserted at top of program.
+    /// Add a statement to be inserted at top of program, after any existing `import` statements.
     pub fn insert_statement(&self, stmt: Statement<'a>) {
         self.stmts.borrow_mut().push(stmt);
     }
-}
\ No newline at end of file
+
+    /// Add a statement to be inserted at `position` relative to the directive prologue and the
+    /// `import` block.
+    pub fn insert_statement_at(&self, position: Position, stmt: Statement<'a>) {
+        let bucket = match position {
+            Position::BeforeImports => &self.before_imports,
+            Position::AfterDirectives => &self.after_directives,
+            Position::AfterImports => &self.stmts,
+        };
+        bucket.borrow_mut().push(stmt);
+    }
+
+    /// Add a statement to be inserted at top of program, keyed on `key`.
+    ///
+    /// `f` is only called, and its statement only enqueued, the first time a given `key` is seen.
+    /// This lets transforms inject a shared prelude statement (a runtime helper, a polyfill guard)
+    /// without coordinating with one another to avoid duplicates.
+    pub fn insert_statement_once(&self, key: &'a str, f: impl FnOnce() -> Statement<'a>) {
+        if self.stmt_keys.borrow_mut().insert(Atom::from(key)) {
+            self.stmts.borrow_mut().push(f());
+        }
+    }
+
+    /// Make `imported` from `source` available in the current scope as `local`.
+    ///
+    /// At the end of the transform, the specifier is merged into the first existing import of the
+    /// same `source` that has no specifiers or only named ones; if there is none, a new `import`
+    /// is added after the last existing import. Repeated requests for the same `(source, imported, local)` triple collapse
+    /// to a single specifier. Use this in preference to building and inserting `import` statements
+    /// by hand so that cooperating transforms do not emit redundant imports.
+    pub fn insert_import(&self, local: Atom<'a>, imported: Atom<'a>, source: Atom<'a>) {
+        self.imports
+            .borrow_mut()
+            .entry(source)
+            .or_insert_with(PendingImport::new)
+            .push(imported, local);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use oxc_allocator::{Allocator, Vec as ArenaVec};
+    use oxc_ast::AstBuilder;
+
+    use super::*;
+
+    fn import_of<'a>(ast: AstBuilder<'a>, source: &'a str) -> Statement<'a> {
+        let import = ast.alloc_import_declaration(
+            SPAN,
+            None,
+            ast.string_literal(SPAN, Atom::from(source), None),
+            None,
+            ImportOrExportKind::Value,
+        );
+        Statement::ImportDeclaration(import)
+    }
+
+    fn named_specifier<'a>(
+        ast: AstBuilder<'a>,
+        imported: &'a str,
+        local: &'a str,
+    ) -> ImportDeclarationSpecifier<'a> {
+        ImportDeclarationSpecifier::ImportSpecifier(ast.alloc_import_specifier(
+            SPAN,
+            ast.module_export_name_identifier_name(SPAN, Atom::from(imported)),
+            ast.binding_identifier(SPAN, Atom::from(local)),
+            ImportOrExportKind::Value,
+        ))
+    }
+
+    fn source_of<'a>(stmt: &Statement<'a>) -> Option<&'a str> {
+        match stmt {
+            Statement::ImportDeclaration(import) => Some(import.source.value.as_str()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn import_anchors_reports_first_and_after_last() {
+        let allocator = Allocator::default();
+        let ast = AstBuilder::new(&allocator);
+
+        let body: ArenaVec<Statement> = ast.vec_from_iter([
+            ast.statement_empty(SPAN),
+            import_of(ast, "a"),
+            ast.statement_empty(SPAN),
+            import_of(ast, "b"),
+        ]);
+        assert_eq!(import_anchors(&body), (1, 4));
+
+        let empty: ArenaVec<Statement> = ast.vec_from_iter([ast.statement_empty(SPAN)]);
+        assert_eq!(import_anchors(&empty), (0, 0));
+    }
+
+    #[test]
+    fn hoists_scattered_imports_preserving_order() {
+        let allocator = Allocator::default();
+        let ast = AstBuilder::new(&allocator);
+
+        let mut body: ArenaVec<Statement> = ast.vec_from_iter([
+            import_of(ast, "a"),
+            ast.statement_empty(SPAN),
+            import_of(ast, "b"),
+            ast.statement_empty(SPAN),
+            import_of(ast, "c"),
+        ]);
+        hoist_scattered_imports(&mut body);
+
+        let sources: Vec<_> = body.iter().filter_map(source_of).collect();
+        assert_eq!(sources, ["a", "b", "c"]);
+        assert!(matches!(body[3], Statement::EmptyStatement(_)));
+        assert!(matches!(body[4], Statement::EmptyStatement(_)));
+    }
+
+    #[test]
+    fn pending_import_dedups_specifiers() {
+        let foo = Atom::from("foo");
+        let bar = Atom::from("bar");
+
+        let mut pending = PendingImport::new();
+        pending.push(foo, foo);
+        pending.push(foo, foo);
+        pending.push(bar, bar);
+
+        assert_eq!(pending.specifiers, vec![(foo, foo), (bar, bar)]);
+    }
+
+    #[test]
+    fn only_named_imports_are_mergeable() {
+        let allocator = Allocator::default();
+        let ast = AstBuilder::new(&allocator);
+
+        let named = ast.import_declaration(
+            SPAN,
+            Some(ast.vec_from_iter([named_specifier(ast, "foo", "foo")])),
+            ast.string_literal(SPAN, Atom::from("x"), None),
+            None,
+            ImportOrExportKind::Value,
+        );
+        assert!(is_mergeable_import(&named));
+
+        let specifiers = named.specifiers.as_ref().unwrap();
+        assert!(named_specifier_exists(specifiers, Atom::from("foo"), Atom::from("foo")));
+        assert!(!named_specifier_exists(specifiers, Atom::from("bar"), Atom::from("bar")));
+
+        let namespace = ast.import_declaration(
+            SPAN,
+            Some(ast.vec_from_iter([ImportDeclarationSpecifier::ImportNamespaceSpecifier(
+                ast.alloc_import_namespace_specifier(
+                    SPAN,
+                    ast.binding_identifier(SPAN, Atom::from("ns")),
+                ),
+            )])),
+            ast.string_literal(SPAN, Atom::from("x"), None),
+            None,
+            ImportOrExportKind::Value,
+        );
+        assert!(!is_mergeable_import(&namespace));
+    }
+
+    #[test]
+    fn insert_statement_once_is_idempotent() {
+        let allocator = Allocator::default();
+        let ast = AstBuilder::new(&allocator);
+
+        let store = TopLevelStatementsStore::new();
+        let mut calls = 0;
+        let mut once = |key| {
+            store.insert_statement_once(key, || {
+                calls += 1;
+                ast.statement_empty(SPAN)
+            });
+        };
+        once("helper");
+        once("helper");
+        once("other");
+
+        assert_eq!(calls, 2);
+        assert_eq!(store.stmts.borrow().len(), 2);
+    }
+}